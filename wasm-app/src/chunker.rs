@@ -0,0 +1,271 @@
+use wasm_bindgen::prelude::*;
+
+/// Deterministic table of pseudo-random 64-bit constants used to roll the
+/// FastCDC fingerprint, generated once with a fixed-seed splitmix64 stream
+/// so chunk boundaries are reproducible across builds and platforms.
+pub(crate) const GEAR: [u64; 256] = [
+    0xC0E16B163A85A4DC, 0x890ACD8DD443C47C, 0xB3889D8A6DC47761, 0x6A0398E528F0AE6A,
+    0x048344ECE48A855E, 0xF175CFEA21871330, 0x391CEEF02702C2FD, 0x4BAF8CAC4784CB12,
+    0x3547744583A3F88E, 0xD9CF2B15C6B6C90E, 0x961FACC76D5FE21C, 0x0094AB49D50F11F9,
+    0xE3211E37BDBEB6DC, 0x62FE6C274FF3511A, 0x5AC30B329FDF0574, 0x1450582C6B65B406,
+    0x7A30FCC7888EB791, 0x5540F5BA6A15576E, 0x16CEF0559096D3E9, 0x2CF8F14B06874899,
+    0xC9C9263B6E2CE103, 0xD6FF920B0A9FAA6D, 0x53192697DB998DC1, 0x73EA9B9BC7CD18D7,
+    0x102713F872C33FCE, 0xF4183A0E5D2A033E, 0x71B63E307EEBB517, 0xDA61F5713D036000,
+    0x46EB7409AE691B21, 0xB23AD691D6707698, 0x67C8FE11D22FC4B9, 0x7EB4661419481338,
+    0x98077547FB070EFC, 0x1EE63336C2E3A9A8, 0xBC353656348C36F6, 0xCE3898CBF1BB1BD8,
+    0x265B1C23C82915CB, 0xFD1948C91687E355, 0xD976893961980FFA, 0x336E77A6288E4C34,
+    0x16F8956D7B76D269, 0xDA7CD844690D4669, 0x1E8CF85F253A581E, 0x3EA68129E923E53A,
+    0xA080A077C9E9FD79, 0x4469A19C673C14CF, 0xBD5B9351B2D0963C, 0xB46A749CAD9DF6B7,
+    0x07DA714E59C7D362, 0x393A84BB5AF17618, 0xB3AE08F3C86DFC0C, 0x642A350ED7C82C93,
+    0x547BDEC029CD3FA3, 0x778DEBB21B67FC3D, 0xB1E26D886EAED22B, 0x49FB5996898A7303,
+    0x5E245BCEC3E007B3, 0x1F6818E4A739F61B, 0xAD694562D6313AFF, 0xDED7C324E96E3A09,
+    0x0E181EF86A661CF8, 0x675448D833AC146B, 0xF047E1B493D6B255, 0xE3D9F8B33D92678C,
+    0x62648DB4D3B1B3AC, 0x5E772E6B32DED778, 0x6BC2EA32285BAD33, 0x298B58C7B2262C2D,
+    0x89A142E7A847C68F, 0x07B170D776F29A64, 0x754B9D28182FD07F, 0x934990332438604C,
+    0xA1AB48A85CC22BBB, 0xFF5AA2D675545595, 0x32A5A207C5C3EED3, 0xD9970E23AEBB3D51,
+    0xD9D01979FC161649, 0x437A2ED7A4FCA264, 0x30FA485D263C4DD1, 0xAAB6790590CB5B06,
+    0x65091913E11E2CFA, 0x51B90F06B259B46B, 0x8289D10138B1D6B4, 0x88AE7E8730E361FB,
+    0x0833A622304C447B, 0xE2E55431BF4B1B54, 0xDDE9371FC120D32F, 0x5751A8D978CE73DD,
+    0xBF1F19E0E1FBD33D, 0x75374F1247E3CDAA, 0x9F1CA64EB4D3CE97, 0x38136F3A3D5ACE59,
+    0xD47963DBF7F8DC43, 0xD87428FF43DD9D86, 0x2607E8BECE834053, 0x3C7A84FA12044C87,
+    0x8C7F4BFAC5F7E4BB, 0xED4A244966996F87, 0x36C97138AF16E719, 0x08D81534DEDB7662,
+    0xAC7C55978241AFC4, 0xDF1B8863C9332CE7, 0x620EE7F218EA0997, 0x38D1DF383CE89B65,
+    0xE719097929758713, 0x9EC6CD248C58AD3C, 0xF54BD98A78D9F340, 0x6498BC6124519DF3,
+    0x198E656271E64FA2, 0xA43FD5DD0D813097, 0x35AD65FEA929819A, 0x2F00139D2A8CD90C,
+    0x155F41D97478845C, 0x3F2B6A8CFEA779B9, 0x4B7264199D7C962A, 0xA26165F55B57273F,
+    0xB7A6F3F0ECF5B89F, 0x8E0692470E1EE509, 0x23234DA5964B213A, 0x6461D9C18FB4C2B9,
+    0x9C44CAC712B73113, 0x93DE0E8D937A2DA0, 0x88C84529E3843D70, 0x70DAAD40227330CE,
+    0x7AB855C449EC8ACA, 0xC8DE7A81906C8BE8, 0x5F5627DF47641DDA, 0xDD60BF81E2586CBC,
+    0x3CFC1BA44EAF2468, 0x405A9309613AD882, 0x4DE7EB21B0277F28, 0x86E512678E4DD45A,
+    0x0F1286EFD6BDD066, 0x1C8ACA34C2FA6773, 0x1DA8E48B2342E347, 0x1890DCD0A94893E7,
+    0x2B1AAF97EF6B4DFF, 0xB32B16249647A7EC, 0x9FB5F0BCED31EA58, 0x3D78F7907627C61F,
+    0x1841958C7D191F94, 0xA18A85A96A78B19E, 0x631E9ABBB0213210, 0x3DAB614952CC05A9,
+    0x017020B874BEABD6, 0xFA59DA85E751094C, 0x29CD811450B5412E, 0x8D15C850AF2489A8,
+    0x950B3BDD58D563A0, 0x836CB8F306D51F7E, 0x4065EFDE02B744E8, 0xB9BAECB669369D99,
+    0x7B378C9248D47DC4, 0x4DDD25D48CDC6168, 0xA732D6380105F470, 0x75C8D0927BB9C613,
+    0x6785A012497A2D75, 0xFFCA85E4AC7617E9, 0xC6F2129203F39492, 0x3ED2BC376029332E,
+    0xD0DC8D146F7E2680, 0x513F8ED97341B4A1, 0x4324394CFA366D32, 0x7CBEA6EE7DA29A4A,
+    0x69707125AC82ECFA, 0xDD4BA7A8ED6C0EF7, 0x100210A42564A9EF, 0xAF1101E77E76C1C2,
+    0x140A33B32394451B, 0xCE3748EBE86FD0F9, 0x763B94236A3C95DC, 0x0E82087DBE388CE4,
+    0x8A3F991981C24D6E, 0x31B399F558C60586, 0xF50EA2C64AFDFE9B, 0x6C02449C992FF889,
+    0x7914A6531AEEB744, 0xB75F86F73F2F4EC2, 0x1BDB24C7BD571DF8, 0x06E4E518AE8F033E,
+    0xFFE622DAB44F3689, 0xF2792F1385DB0E95, 0x2AAD6FF4838907B8, 0x0D649D2B9341ACCA,
+    0x2AEF8AC693C156CD, 0xB86C9E57FA18942E, 0xE85E3CF930ED3877, 0xB3FB466DD31F94A2,
+    0xAC8D03C007F25604, 0xA9EEC498626FF508, 0xF47BE033DDA3F9B0, 0xA4F748B538E6F27D,
+    0xC01BB10959D5E985, 0x89079DE7DDA37D8F, 0xD7007BA815CC0658, 0xC4DA1BB45A7B871A,
+    0x98185BA52F9D9CD4, 0x4242C91A500844E5, 0x07965F1AA6863C5D, 0x0359CCAAD9AEA599,
+    0xE7A54BF05004EDDB, 0x333AA1CD725FF5E8, 0x94C18D8184570964, 0xEE0303AF7E757A57,
+    0xBBC38705003C82EC, 0xC57A6BBDBB7EDFBD, 0xBAEA4E697C235EE2, 0x9F1ED9C9B4707EA2,
+    0x3845A969B77941F0, 0x1F02624C80D73CE6, 0x4820B4E1649D1DDC, 0x77D1259B2F0BE5FB,
+    0xA495F4FDBA5CCCDD, 0x5CE421E295346C68, 0x0DFD63ADC1C5BC74, 0x570045B98CBC93E3,
+    0x5B7317CD17A15F04, 0x6DEFB13E4A48FA9C, 0x9D2540358539F109, 0xDFF1D3DB7AF0541B,
+    0xA786C0D906DF090E, 0x9C8AA8553F5DB609, 0x2D5D59B48454AB11, 0x73FBFBFD57360323,
+    0xE045969A1FE274D6, 0xB374B31CCC1C9668, 0xEE53C1D82D9CED9C, 0x02EE16F7445F3D27,
+    0x43D17009ACF06ED8, 0xD17F5BAF03DD6E26, 0xBDDF2289ED7719FF, 0xF9B980D54F117273,
+    0xCDD05DC90B2C3B5B, 0xAE6DF7DD9D557455, 0xA6A0E6779F5DFB3F, 0xD85269B48DE6F619,
+    0x43B0855155163E1C, 0x716AA342EAA75E67, 0xF601D8D15E1709AE, 0x9CE1C4F19D6C405B,
+    0x8E5D480BF2121C70, 0x5CD643CB24CBAA78, 0x44ECFA2A75CA3A34, 0x390F2EDDEA3099A2,
+    0xDFEA67149DA0609F, 0xB734297101779A59, 0xC3F3700CBB0AFE9F, 0x403CAE0119D1BB35,
+    0x23853B00D0E1076B, 0x63DC284AE4CF5983, 0x252721131CFE91AE, 0xDBE6D98B3113E9D6,
+    0xF3F923744C247687, 0x01EF9061730E4AB6, 0x7F2A753307B3391C, 0xFD4CBB1B3007D376,
+];
+
+/// A chunk boundary as reported to JS: the byte offset into the original
+/// buffer and the chunk's length.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChunkBoundary {
+    offset: u32,
+    length: u32,
+}
+
+#[wasm_bindgen]
+impl ChunkBoundary {
+    #[wasm_bindgen(getter)]
+    pub fn offset(&self) -> u32 {
+        self.offset
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn length(&self) -> u32 {
+        self.length
+    }
+}
+
+/// Mask with roughly `bits` one-bits, used to bias the rolling fingerprint
+/// test toward a target average chunk size (FastCDC's "normalized chunking").
+fn mask_with_bits(bits: u32) -> u64 {
+    if bits == 0 {
+        0
+    } else {
+        u64::MAX >> (64 - bits.min(64))
+    }
+}
+
+/// Derives the stricter (`mask_s`) and looser (`mask_l`) cut masks for a
+/// given `avg_size`, shared by the one-shot chunker and the streaming cutter.
+pub(crate) fn masks_for_avg_size(avg_size: usize) -> (u64, u64) {
+    let bits = (avg_size.max(1) as f64).log2().round() as u32;
+    (mask_with_bits(bits.saturating_add(2)), mask_with_bits(bits.saturating_sub(2)))
+}
+
+/// Splits `data` into content-defined chunks using FastCDC with normalized
+/// chunking, returning each chunk's offset and length.
+///
+/// `min_size`/`avg_size`/`max_size` bound the chunk sizes; `avg_size` drives
+/// the mask bit-count (`log2(avg_size)`), with a stricter mask used below the
+/// average and a looser mask used above it so cut points cluster around the
+/// target size.
+#[wasm_bindgen]
+pub fn chunk_buffer(data: &[u8], min_size: u32, avg_size: u32, max_size: u32) -> Vec<ChunkBoundary> {
+    let min_size = min_size as usize;
+    let avg_size = avg_size.max(1) as usize;
+    let max_size = max_size as usize;
+
+    let (mask_s, mask_l) = masks_for_avg_size(avg_size);
+
+    let mut boundaries = Vec::new();
+    let mut start = 0usize;
+
+    while start < data.len() {
+        let remaining = data.len() - start;
+
+        let mut fp: u64 = 0;
+        let mut cut = None;
+
+        // Bytes before min_size are never tested for a cut.
+        let skip = min_size.min(remaining);
+        let mut len = skip;
+
+        let mut i = start + skip;
+        while i < data.len() {
+            let b = data[i];
+            fp = (fp << 1).wrapping_add(GEAR[b as usize]);
+            len += 1;
+
+            let mask = if len < avg_size { mask_s } else { mask_l };
+            if fp & mask == 0 {
+                cut = Some(len);
+                break;
+            }
+            if len >= max_size {
+                cut = Some(len);
+                break;
+            }
+            i += 1;
+        }
+
+        let length = cut.unwrap_or(len).min(data.len() - start);
+        boundaries.push(ChunkBoundary {
+            offset: start as u32,
+            length: length as u32,
+        });
+        start += length;
+    }
+
+    boundaries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pseudo_random_bytes(len: usize, seed: u64) -> Vec<u8> {
+        let mut state = seed;
+        (0..len)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                (state & 0xff) as u8
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_chunks_reassemble_to_input() {
+        let data = pseudo_random_bytes(200_000, 42);
+        let boundaries = chunk_buffer(&data, 1024, 4096, 16384);
+
+        let mut reassembled = Vec::with_capacity(data.len());
+        for b in &boundaries {
+            let start = b.offset() as usize;
+            let end = start + b.length() as usize;
+            reassembled.extend_from_slice(&data[start..end]);
+        }
+        assert_eq!(reassembled, data);
+    }
+
+    #[test]
+    fn test_respects_size_bounds() {
+        let data = pseudo_random_bytes(200_000, 7);
+        let boundaries = chunk_buffer(&data, 1024, 4096, 16384);
+
+        for (i, b) in boundaries.iter().enumerate() {
+            let is_last = i == boundaries.len() - 1;
+            assert!(b.length() as usize <= 16384);
+            if !is_last {
+                assert!(b.length() as usize >= 1024);
+            }
+        }
+    }
+
+    #[test]
+    fn test_empty_input_yields_no_chunks() {
+        let boundaries = chunk_buffer(&[], 1024, 4096, 16384);
+        assert!(boundaries.is_empty());
+    }
+
+    #[test]
+    fn test_boundaries_stable_under_edit_near_site() {
+        let mut data = pseudo_random_bytes(100_000, 99);
+        let original = chunk_buffer(&data, 1024, 4096, 16384);
+
+        // Insert a single leading byte partway through the buffer; chunks
+        // entirely before the edit site should be untouched.
+        let edit_at = 50_000;
+        data.insert(edit_at, 0xAB);
+        let edited = chunk_buffer(&data, 1024, 4096, 16384);
+
+        let unaffected_original: Vec<_> = original
+            .iter()
+            .take_while(|b| (b.offset() + b.length()) as usize <= edit_at)
+            .collect();
+        let unaffected_edited: Vec<_> = edited
+            .iter()
+            .take_while(|b| (b.offset() + b.length()) as usize <= edit_at)
+            .collect();
+
+        assert_eq!(unaffected_original.len(), unaffected_edited.len());
+        for (a, b) in unaffected_original.iter().zip(unaffected_edited.iter()) {
+            assert_eq!(a.offset(), b.offset());
+            assert_eq!(a.length(), b.length());
+        }
+
+        // The point of content-defined chunking is that only a handful of
+        // cut points near the edit move; everything downstream of that
+        // should resync and line up again, just shifted by the one inserted
+        // byte. Give the cutter up to `max_size` bytes of slack to resync,
+        // then require the cut points beyond that to match exactly.
+        let resync_after = edit_at + 16384;
+        let original_cuts_after_resync: Vec<usize> =
+            original.iter().map(|b| b.offset() as usize).filter(|&o| o > resync_after).collect();
+        let edited_cuts_after_resync: Vec<usize> =
+            edited.iter().map(|b| b.offset() as usize).filter(|&o| o > resync_after).collect();
+
+        assert!(!original_cuts_after_resync.is_empty(), "test data too short to observe resync");
+        assert_eq!(
+            original_cuts_after_resync.len(),
+            edited_cuts_after_resync.len(),
+            "cut points well after the edit site should resync to the same count"
+        );
+        for (original_offset, edited_offset) in original_cuts_after_resync.iter().zip(edited_cuts_after_resync.iter())
+        {
+            assert_eq!(
+                *edited_offset,
+                original_offset + 1,
+                "cut points well after the edit site should resync, shifted by the one inserted byte"
+            );
+        }
+    }
+}
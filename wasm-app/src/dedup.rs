@@ -0,0 +1,112 @@
+use std::collections::HashSet;
+use wasm_bindgen::prelude::*;
+
+/// Tracks which plaintext chunk hashes (as produced by [`hash_chunk`] /
+/// [`hmac_chunk`](crate::hmac_chunk)) have already been seen in the current
+/// session, so the caller only has to ship a chunk's bytes the first time
+/// its hash appears and a reference to that hash on every repeat.
+///
+/// [`hash_chunk`]: crate::hash_chunk
+#[wasm_bindgen]
+pub struct DedupIndex {
+    seen: HashSet<String>,
+}
+
+#[wasm_bindgen]
+impl DedupIndex {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> DedupIndex {
+        DedupIndex { seen: HashSet::new() }
+    }
+
+    /// Returns `true` if `hash` has already been recorded in this index.
+    pub fn is_duplicate(&self, hash: &str) -> bool {
+        self.seen.contains(hash)
+    }
+
+    /// Records `hash` as seen. Returns `true` if this is the first time the
+    /// hash has been recorded (i.e. the chunk should be sent in full), or
+    /// `false` if it was already present (i.e. only a reference is needed).
+    pub fn record(&mut self, hash: &str) -> bool {
+        self.seen.insert(hash.to_string())
+    }
+
+    /// Removes a single hash from the index, e.g. if its storage was evicted.
+    pub fn evict(&mut self, hash: &str) -> bool {
+        self.seen.remove(hash)
+    }
+
+    /// Clears the entire index.
+    pub fn reset(&mut self) {
+        self.seen.clear();
+    }
+
+    /// Number of distinct hashes currently recorded.
+    pub fn len(&self) -> usize {
+        self.seen.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.seen.is_empty()
+    }
+}
+
+impl Default for DedupIndex {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_first_sighting_is_not_duplicate() {
+        let mut index = DedupIndex::new();
+        assert!(!index.is_duplicate("abc"));
+        assert!(index.record("abc"));
+    }
+
+    #[test]
+    fn test_repeated_chunk_is_duplicate() {
+        let mut index = DedupIndex::new();
+        assert!(index.record("abc"));
+        assert!(index.is_duplicate("abc"));
+        assert!(!index.record("abc"));
+        assert_eq!(index.len(), 1);
+    }
+
+    #[test]
+    fn test_feeding_same_chunk_twice_yields_one_entry_one_reference() {
+        let mut index = DedupIndex::new();
+
+        // First time: not a duplicate, gets recorded -> one stored entry.
+        let first_is_new = !index.is_duplicate("hash-1") && index.record("hash-1");
+        // Second time: is a duplicate -> one reference, no new storage.
+        let second_is_duplicate = index.is_duplicate("hash-1");
+
+        assert!(first_is_new);
+        assert!(second_is_duplicate);
+        assert_eq!(index.len(), 1);
+    }
+
+    #[test]
+    fn test_evict_removes_entry() {
+        let mut index = DedupIndex::new();
+        index.record("abc");
+        assert!(index.evict("abc"));
+        assert!(!index.is_duplicate("abc"));
+        assert!(!index.evict("abc"));
+    }
+
+    #[test]
+    fn test_reset_clears_index() {
+        let mut index = DedupIndex::new();
+        index.record("abc");
+        index.record("def");
+        index.reset();
+        assert!(index.is_empty());
+        assert_eq!(index.len(), 0);
+    }
+}
@@ -0,0 +1,111 @@
+use blake2::Blake2b512;
+use hmac::{Hmac, Mac, SimpleHmac};
+use sha2::{Digest, Sha256};
+use wasm_bindgen::prelude::*;
+
+/// Digest algorithm used for content addressing, selectable per call so the
+/// hash, like the compression method, can be swapped out without changing
+/// the calling convention.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HashMethod {
+    Sha256 = 0,
+    Blake2b = 1,
+}
+
+fn hex_encode(bytes: &[u8]) -> String {
+    use std::fmt::Write;
+    let mut out = String::with_capacity(bytes.len() * 2);
+    for byte in bytes {
+        write!(out, "{byte:02x}").expect("writing to a String cannot fail");
+    }
+    out
+}
+
+/// Hashes `chunk` with `method`, returning the hex-encoded digest.
+#[wasm_bindgen]
+pub fn hash_chunk(chunk: &[u8], method: HashMethod) -> String {
+    match method {
+        HashMethod::Sha256 => {
+            let mut hasher = Sha256::new();
+            hasher.update(chunk);
+            hex_encode(&hasher.finalize())
+        }
+        HashMethod::Blake2b => {
+            let mut hasher = Blake2b512::new();
+            hasher.update(chunk);
+            hex_encode(&hasher.finalize())
+        }
+    }
+}
+
+/// Computes a keyed HMAC over `chunk`'s plaintext using `method` as the
+/// underlying digest, returning a hex-encoded tag. This is the storage
+/// address an HMAC-based content-addressing scheme derives from the
+/// plaintext: it lets two chunks with identical content collapse to the same
+/// reference without ever comparing ciphertext.
+#[wasm_bindgen]
+pub fn hmac_chunk(key: &[u8], chunk: &[u8], method: HashMethod) -> String {
+    match method {
+        HashMethod::Sha256 => {
+            let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+            mac.update(chunk);
+            hex_encode(&mac.finalize().into_bytes())
+        }
+        HashMethod::Blake2b => {
+            let mut mac = SimpleHmac::<Blake2b512>::new_from_slice(key).expect("HMAC accepts keys of any length");
+            mac.update(chunk);
+            hex_encode(&mac.finalize().into_bytes())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hash_chunk_is_deterministic() {
+        let data = b"some data";
+        assert_eq!(hash_chunk(data, HashMethod::Sha256), hash_chunk(data, HashMethod::Sha256));
+        assert_eq!(hash_chunk(data, HashMethod::Blake2b), hash_chunk(data, HashMethod::Blake2b));
+    }
+
+    #[test]
+    fn test_hash_chunk_differs_between_methods() {
+        let data = b"some data";
+        assert_ne!(hash_chunk(data, HashMethod::Sha256), hash_chunk(data, HashMethod::Blake2b));
+    }
+
+    #[test]
+    fn test_hash_chunk_differs_on_different_input() {
+        assert_ne!(hash_chunk(b"a", HashMethod::Sha256), hash_chunk(b"b", HashMethod::Sha256));
+    }
+
+    #[test]
+    fn test_hash_chunk_sha256_known_vector() {
+        // sha256("") == e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855
+        assert_eq!(
+            hash_chunk(b"", HashMethod::Sha256),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+    }
+
+    #[test]
+    fn test_hmac_chunk_is_keyed() {
+        let data = b"some data";
+        let tag_a = hmac_chunk(b"key-a", data, HashMethod::Sha256);
+        let tag_b = hmac_chunk(b"key-b", data, HashMethod::Sha256);
+        assert_ne!(tag_a, tag_b);
+    }
+
+    #[test]
+    fn test_hmac_chunk_same_plaintext_same_key_matches() {
+        let data = b"duplicate chunk contents";
+        let key = b"session-key";
+        assert_eq!(
+            hmac_chunk(key, data, HashMethod::Blake2b),
+            hmac_chunk(key, data, HashMethod::Blake2b)
+        );
+    }
+}
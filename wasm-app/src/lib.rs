@@ -1,64 +1,23 @@
 use wasm_bindgen::prelude::*;
 
-// Compression (skeleton, no real compression yet)
-#[wasm_bindgen]
-pub fn compress_chunk(chunk: &[u8]) -> Vec<u8> {
-    // TODO: Implement real compression (e.g., flate2, lz4)
-    chunk.to_vec()
-}
-
-// Decompression (skeleton, no real decompression yet)
-#[wasm_bindgen]
-pub fn decompress_chunk(chunk: &[u8]) -> Vec<u8> {
-    // TODO: Implement real decompression
-    chunk.to_vec()
-}
-
-// Hashing (optional, skeleton)
-#[wasm_bindgen]
-pub fn hash_chunk(_chunk: &[u8]) -> String {
-    // TODO: Implement real hashing if needed
-    "dummy_hash_123".to_string()
-}
+mod chunker;
+mod compression;
+mod dedup;
+mod hashing;
+mod streaming;
+
+pub use chunker::{chunk_buffer, ChunkBoundary};
+pub use compression::{compress_chunk, decompress_chunk, CompressionMethod};
+pub use dedup::DedupIndex;
+pub use hashing::{hash_chunk, hmac_chunk, HashMethod};
+pub use streaming::StreamProcessor;
 
 // Called when the WASM module is instantiated
 #[wasm_bindgen(start)]
 pub fn main() {
     // Log when WASM module is loaded
     web_sys::console::log_1(&"🚀 WASM module loaded and initialized!".into());
-    web_sys::console::log_1(&"📦 Available functions: compress_chunk, decompress_chunk, hash_chunk".into());
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_compress_chunk_basic() {
-        let input = b"hello world";
-        let result = compress_chunk(input);
-        assert_eq!(result, input);
-    }
-
-    #[test]
-    fn test_decompress_chunk_basic() {
-        let input = b"test data";
-        let result = decompress_chunk(input);
-        assert_eq!(result, input);
-    }
-
-    #[test]
-    fn test_hash_chunk_basic() {
-        let input = b"some data";
-        let result = hash_chunk(input);
-        assert_eq!(result, "dummy_hash_123");
-    }
-
-    #[test]
-    fn test_compress_decompress_roundtrip() {
-        let original = b"roundtrip test";
-        let compressed = compress_chunk(original);
-        let decompressed = decompress_chunk(&compressed);
-        assert_eq!(original, decompressed.as_slice());
-    }
+    web_sys::console::log_1(
+        &"📦 Available functions: compress_chunk, decompress_chunk, chunk_buffer, hash_chunk, hmac_chunk, StreamProcessor".into(),
+    );
 }
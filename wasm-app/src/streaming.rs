@@ -0,0 +1,239 @@
+use js_sys::Uint8Array;
+use wasm_bindgen::prelude::*;
+
+use crate::chunker::{masks_for_avg_size, GEAR};
+use crate::compression::{compress_chunk, CompressionMethod};
+use crate::hashing::{hash_chunk, HashMethod};
+
+/// Receives one `(compressed chunk bytes, hex hash)` pair per completed
+/// chunk. Implemented by the JS-facing callback wrapper below and, for
+/// tests, by a plain in-memory collector so the cutting logic can be
+/// exercised without a JS runtime.
+trait Sink {
+    fn deliver(&mut self, bytes: &[u8], hash: &str) -> Result<(), JsError>;
+}
+
+/// Adapts a JS callback (`bytes: Uint8Array, hash: string`) to [`Sink`].
+struct JsCallbackSink(js_sys::Function);
+
+impl Sink for JsCallbackSink {
+    fn deliver(&mut self, bytes: &[u8], hash: &str) -> Result<(), JsError> {
+        let bytes_arg = Uint8Array::from(bytes);
+        self.0
+            .call2(&JsValue::NULL, &bytes_arg.into(), &JsValue::from_str(hash))
+            .map(|_| ())
+            .map_err(|e| JsError::new(&format!("stream callback threw: {e:?}")))
+    }
+}
+
+/// Streams data through the FastCDC chunker, compression and hashing
+/// pipeline in bounded memory, delivering one `(bytes, hash)` pair per
+/// completed chunk to a [`Sink`] instead of buffering the whole transfer
+/// into one `Vec`.
+///
+/// The only memory held between `push` calls is the bytes of the chunk
+/// currently being assembled, which never exceeds `max_size`.
+///
+/// The cut logic below (skip `min_size`, test the rolling fingerprint
+/// against `mask_s`/`mask_l`, force a cut at `max_size`, flush whatever
+/// remains at end of input) must stay byte-for-byte identical to
+/// [`chunk_buffer`](crate::chunker::chunk_buffer)'s inner loop: `finish`
+/// flushing a trailing chunk of any size is only correct because the
+/// one-shot chunker does the same instead of special-casing short tails.
+struct StreamCutter<S: Sink> {
+    min_size: usize,
+    avg_size: usize,
+    max_size: usize,
+    mask_s: u64,
+    mask_l: u64,
+    compression: CompressionMethod,
+    hash_method: HashMethod,
+    sink: S,
+
+    current: Vec<u8>,
+    fp: u64,
+}
+
+impl<S: Sink> StreamCutter<S> {
+    fn new(min_size: usize, avg_size: usize, max_size: usize, compression: CompressionMethod, hash_method: HashMethod, sink: S) -> Self {
+        let avg_size = avg_size.max(1);
+        let (mask_s, mask_l) = masks_for_avg_size(avg_size);
+        StreamCutter {
+            min_size,
+            avg_size,
+            max_size,
+            mask_s,
+            mask_l,
+            compression,
+            hash_method,
+            sink,
+            current: Vec::new(),
+            fp: 0,
+        }
+    }
+
+    /// Pushes the next slice of input through the pipeline, delivering a
+    /// completed chunk to the sink for each boundary reached along the way.
+    fn push(&mut self, data: &[u8]) -> Result<(), JsError> {
+        for &byte in data {
+            self.current.push(byte);
+
+            if self.current.len() <= self.min_size {
+                continue;
+            }
+
+            self.fp = (self.fp << 1).wrapping_add(GEAR[byte as usize]);
+            let mask = if self.current.len() < self.avg_size { self.mask_s } else { self.mask_l };
+
+            if self.fp & mask == 0 || self.current.len() >= self.max_size {
+                self.emit()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Flushes any trailing partial chunk as a final chunk.
+    fn finish(&mut self) -> Result<(), JsError> {
+        if !self.current.is_empty() {
+            self.emit()?;
+        }
+        Ok(())
+    }
+
+    fn emit(&mut self) -> Result<(), JsError> {
+        let plaintext = std::mem::take(&mut self.current);
+        self.fp = 0;
+
+        let hash = hash_chunk(&plaintext, self.hash_method);
+        let compressed = compress_chunk(&plaintext, self.compression);
+        self.sink.deliver(&compressed, &hash)
+    }
+}
+
+/// WASM-facing streaming encoder: pushes data in arbitrary increments
+/// through the FastCDC chunker, per-chunk compression and hashing, and
+/// invokes a JS callback once per completed chunk instead of buffering the
+/// whole transfer into one allocation. Call [`finish`](StreamProcessor::finish)
+/// once all input has been pushed to flush the trailing partial chunk.
+#[wasm_bindgen]
+pub struct StreamProcessor(StreamCutter<JsCallbackSink>);
+
+#[wasm_bindgen]
+impl StreamProcessor {
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        min_size: u32,
+        avg_size: u32,
+        max_size: u32,
+        compression: CompressionMethod,
+        hash_method: HashMethod,
+        callback: js_sys::Function,
+    ) -> StreamProcessor {
+        StreamProcessor(StreamCutter::new(
+            min_size as usize,
+            avg_size as usize,
+            max_size as usize,
+            compression,
+            hash_method,
+            JsCallbackSink(callback),
+        ))
+    }
+
+    pub fn push(&mut self, data: &[u8]) -> Result<(), JsError> {
+        self.0.push(data)
+    }
+
+    pub fn finish(&mut self) -> Result<(), JsError> {
+        self.0.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pseudo_random_bytes(len: usize, seed: u64) -> Vec<u8> {
+        let mut state = seed;
+        (0..len)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                (state & 0xff) as u8
+            })
+            .collect()
+    }
+
+    #[derive(Default)]
+    struct VecSink {
+        chunks: Vec<(Vec<u8>, String)>,
+    }
+
+    impl Sink for VecSink {
+        fn deliver(&mut self, bytes: &[u8], hash: &str) -> Result<(), JsError> {
+            self.chunks.push((bytes.to_vec(), hash.to_string()));
+            Ok(())
+        }
+    }
+
+    fn one_shot_chunks(data: &[u8], min_size: usize, avg_size: usize, max_size: usize) -> Vec<(Vec<u8>, String)> {
+        crate::chunker::chunk_buffer(data, min_size as u32, avg_size as u32, max_size as u32)
+            .iter()
+            .map(|b| {
+                let plaintext = &data[b.offset() as usize..(b.offset() + b.length()) as usize];
+                (
+                    compress_chunk(plaintext, CompressionMethod::NoCompression),
+                    hash_chunk(plaintext, HashMethod::Sha256),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_streamed_output_matches_one_shot_single_push() {
+        let data = pseudo_random_bytes(50_000, 11);
+        let expected = one_shot_chunks(&data, 256, 1024, 4096);
+
+        let mut cutter =
+            StreamCutter::new(256, 1024, 4096, CompressionMethod::NoCompression, HashMethod::Sha256, VecSink::default());
+        cutter.push(&data).unwrap();
+        cutter.finish().unwrap();
+
+        assert_eq!(cutter.sink.chunks, expected);
+    }
+
+    #[test]
+    fn test_streamed_output_matches_one_shot_across_uneven_pushes() {
+        let data = pseudo_random_bytes(50_000, 11);
+        let expected = one_shot_chunks(&data, 256, 1024, 4096);
+
+        let mut cutter =
+            StreamCutter::new(256, 1024, 4096, CompressionMethod::NoCompression, HashMethod::Sha256, VecSink::default());
+
+        // Push in uneven increments so chunk boundaries span multiple pushes.
+        let windows = [37, 1000, 1, 4096, 500];
+        let mut offset = 0;
+        let mut push_index = 0;
+        while offset < data.len() {
+            let end = (offset + windows[push_index % windows.len()]).min(data.len());
+            cutter.push(&data[offset..end]).unwrap();
+            offset = end;
+            push_index += 1;
+        }
+        cutter.finish().unwrap();
+
+        assert_eq!(cutter.sink.chunks, expected);
+    }
+
+    #[test]
+    fn test_finish_is_a_noop_with_no_pending_data() {
+        let mut cutter =
+            StreamCutter::new(256, 1024, 4096, CompressionMethod::NoCompression, HashMethod::Sha256, VecSink::default());
+        cutter.push(b"short, below min_size").unwrap();
+        cutter.finish().unwrap();
+        let chunks_after_first_finish = cutter.sink.chunks.len();
+
+        cutter.finish().unwrap();
+        assert_eq!(cutter.sink.chunks.len(), chunks_after_first_finish);
+    }
+}